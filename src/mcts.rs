@@ -7,6 +7,7 @@ use std::{
 use hashbrown::HashMap;
 use itertools::Itertools;
 use rand::seq::IndexedRandom;
+use rayon::prelude::*;
 use crate::engine::Engine;
 
 use super::board::*;
@@ -28,6 +29,27 @@ pub fn white_score(board: &Board) -> f32 {
     }
 }
 
+/// Performs a single rollout and returns the evaluation of the final state, together with the
+/// sequence of `(player-to-move, action)` pairs played along the way.
+///
+/// The trace is what RAVE/AMAF needs: an action played by a given player anywhere in the
+/// simulation can update the AMAF statistics of that player's matching edges higher up the tree.
+pub fn rollout_trace(board: &Board) -> (f32, Vec<(Color, Action)>) {
+    let mut seq = Vec::new();
+    let mut new_board = board.clone();
+    loop {
+        let choosen = new_board.actions().choose(&mut rand::rng()).cloned();
+        match choosen {
+            Some(act) => {
+                seq.push((new_board.turn, act.clone()));
+                new_board = new_board.apply(&act);
+            }
+            None => break,
+        }
+    }
+    (white_score(&new_board), seq)
+}
+
 /// Performs a single rollout and returns the evaluation of the final state.
 pub fn rollout(board: &Board) -> f32 {
         let mut choosen : Option<Action> = board.actions().choose(&mut rand::rng()).cloned();
@@ -95,6 +117,10 @@ struct OutEdge {
     visits: Count,
     // Q(s,a): Last known evaluation of the board resulting from the action
     eval: f32,
+    // N_amaf(s,a): number of simulations in which this action was played later by the same player
+    amaf_visits: Count,
+    // Q_amaf(s,a): running mean of those simulations' returns
+    amaf_eval: f32,
 }
 impl OutEdge {
     /// Initializes a new edge for this actions (with a count and eval at 0)
@@ -103,6 +129,8 @@ impl OutEdge {
             action,
             visits: 0,
             eval: 0.,
+            amaf_visits: 0,
+            amaf_eval: 0.,
         }
     }
 }
@@ -129,13 +157,112 @@ pub struct MctsEngine {
     nodes: HashMap<Board, Node>,
     /// weight given to the exploration term in UCB1
     pub exploration_weight: f32,
+    /// When set, the tree is kept between turns: after committing to a move we prune
+    /// `nodes` down to the subtree reachable from the new board (see `advance_root`),
+    /// so the next `select` continues refining an already-warm tree instead of starting cold.
+    /// Has no effect when `num_threads > 1`: the parallel workers build fresh trees every turn.
+    pub reuse_tree: bool,
+    /// Number of independent searches run in parallel (root parallelization). `1` keeps the
+    /// single-threaded behaviour; higher values spawn that many workers over rayon.
+    pub num_threads: usize,
 }
 impl MctsEngine {
     pub fn new(exploration_weight: f32) -> MctsEngine {
         MctsEngine {
             nodes: HashMap::new(),
             exploration_weight,
+            reuse_tree: false,
+            num_threads: 1,
+        }
+    }
+
+    /// Builder-style setter for the number of root-parallel workers.
+    pub fn with_threads(mut self, num_threads: usize) -> MctsEngine {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// Runs `num_threads` fully independent searches on `root` until the deadline, each with its
+    /// own `HashMap<Board, Node>` and thread-local RNG, then merges them (root parallelization).
+    ///
+    /// Merging sums each action's `visits` across the workers and recomputes a visit-weighted
+    /// `eval`, so an action agreed upon by several trees dominates the noise of any single run.
+    /// Returns the merged root edges as `(action, visits, eval)`, or an empty vector when the
+    /// root has no available action.
+    fn search_root_parallel(&self, root: &Board, deadline: Instant) -> Vec<(Action, Count, f32)> {
+        let exploration_weight = self.exploration_weight;
+        // Canonical edge order: every worker builds its root node from `board.actions()`, so the
+        // edges line up positionally and we can merge by index without requiring `Action: Hash`.
+        let actions = root.actions();
+        if actions.is_empty() {
+            return Vec::new();
+        }
+
+        // Each worker grows its own tree from the same root and reports its root edge statistics,
+        // aligned to `actions` (empty when the deadline left it no time to expand the root, so that
+        // worker adds nothing to the merge).
+        let per_worker: Vec<Vec<(Count, f32)>> = (0..self.num_threads)
+            .into_par_iter()
+            .map(|_| {
+                let mut local = MctsEngine::new(exploration_weight);
+                while Instant::now() < deadline {
+                    local.playout(root);
+                }
+                local
+                    .nodes
+                    .get(root)
+                    .map(|node| node.out_edges.iter().map(|e| (e.visits, e.eval)).collect_vec())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        // Pool per-action: total visits and a visit-weighted sum of evals, then normalize.
+        actions
+            .into_iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let mut visits: Count = 0;
+                let mut weighted_eval = 0.;
+                for edges in per_worker.iter() {
+                    if let Some(&(v, e)) = edges.get(i) {
+                        visits += v;
+                        weighted_eval += (v as f32) * e;
+                    }
+                }
+                let eval = if visits > 0 {
+                    weighted_eval / visits as f32
+                } else {
+                    0.
+                };
+                (action, visits, eval)
+            })
+            .collect_vec()
+    }
+
+    /// Prunes the tree down to the subtree reachable from `new_board`, keeping the accumulated
+    /// `count`/`eval` statistics of the surviving nodes and evicting everything else.
+    ///
+    /// The new board becomes the root of the next search: once an action has been committed
+    /// the sibling subtrees can never be revisited, so dropping them keeps `nodes` bounded across
+    /// a long game while the retained statistics give the next `select` a warm start.
+    pub fn advance_root(&mut self, new_board: &Board) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        // Collect every board reachable from the new root by following the stored edges.
+        let mut reachable: HashMap<Board, ()> = HashMap::new();
+        let mut stack = vec![new_board.clone()];
+        while let Some(board) = stack.pop() {
+            if reachable.insert(board.clone(), ()).is_some() {
+                continue;
+            }
+            if let Some(node) = self.nodes.get(&board) {
+                for edge in node.out_edges.iter() {
+                    stack.push(board.apply(&edge.action));
+                }
+            }
         }
+        self.nodes.retain(|board, _| reachable.contains_key(board));
     }
 }
 
@@ -144,15 +271,19 @@ impl MctsEngine {
     pub fn select_ucb1(&self, board: &Board) -> Option<Action> {
         //debug_assert!(self.nodes.contains_key(board));
         //let mut choosen : Option<Action>;
+        // `eval`/`amaf_eval` are absolute white scores (1 = White win), so White maximizes the raw
+        // value and Black minimizes it — matching the final-move tie-break and the minimax engine.
         let T:f32;
         match board.turn {
-            Color::Black => T=1.,
-            Color::White =>T=-1.,
+            Color::Black => T=-1.,
+            Color::White =>T=1.,
         }
-        let mut max_ucb1:f32 = 0.;
+        let mut max_ucb1:f32 = f32::NEG_INFINITY;
         let mut return_action :Option<Action>= None;
         let node = self.nodes.get(board).unwrap();
         let n : f32 = node.count as f32;
+        // small tunable constant controlling how fast the AMAF term is phased out (Silver's `b`)
+        let b: f32 = 0.05;
         let mut q_sa : f32;
         let mut n_sa : f32;
         let mut c :f32;
@@ -160,11 +291,25 @@ impl MctsEngine {
         let mut ucb1:f32;
         // a nettoyer plus tard : retirer les declarations useless
         for arc in node.out_edges.iter(){
-            q_sa = arc.eval;
             n_sa = arc.visits as f32;
+            let n_amaf = arc.amaf_visits as f32;
+            // RAVE blend: (1-β)·Q(s,a) + β·Q_amaf(s,a), with β shrinking as real visits accumulate.
+            // When there are no real visits the edge falls back to pure AMAF; guard the 0/0 case.
+            let denom = n_sa + n_amaf + 4. * n_sa * n_amaf * b * b;
+            let beta = if denom > 0. { n_amaf / denom } else { 0. };
+            q_sa = (1. - beta) * arc.eval + beta * arc.amaf_eval;
             c = self.exploration_weight;
-            sqrt = 2.*(f32::log(n,10.)/n_sa).sqrt();
-            ucb1 = T * q_sa + self.exploration_weight * sqrt;
+            // Unvisited edges (`n_sa == 0`) would make the UCB1 exploration term diverge to +∞ and
+            // drown out the blended/AMAF value, so every such edge would tie and be picked in
+            // iteration order. Give them a finite first-play-urgency bonus instead, so the pure-AMAF
+            // `q_sa` still decides between them — this is what makes the tree usable with few visits.
+            const FIRST_PLAY_URGENCY: f32 = 1.;
+            sqrt = if n_sa > 0. {
+                2. * (f32::log(n, 10.) / n_sa).sqrt()
+            } else {
+                FIRST_PLAY_URGENCY
+            };
+            ucb1 = T * q_sa + c * sqrt;
             if (ucb1>max_ucb1){
                 max_ucb1 = ucb1;
                 return_action = Some(arc.action.clone());
@@ -175,56 +320,147 @@ impl MctsEngine {
     }
 
     /// Performs a playout for this board (s) and returns the (updated) evaluation of the board (Q(s))
-    fn playout(&mut self, board: &Board) -> f32 {
-        let mut eval = rollout(board);
+    /// — for a node already in the tree, this is the raw return of the simulation just run (the value
+    /// backed up along the path), not the node's running mean — together with the sequence of
+    /// `(player-to-move, action)` pairs played below `s` (tree descent followed by the random
+    /// rollout), used to feed the AMAF statistics on the way back up.
+    fn playout(&mut self, board: &Board) -> (f32, Vec<(Color, Action)>) {
         if !self.nodes.contains_key(board) {
+            let (eval, seq) = rollout_trace(board);
             self.nodes.insert(board.clone(), Node::init(board.clone(), eval));
-            return eval
-            
+            (eval, seq)
         } else {
-            
-            match (self.select_ucb1(board)){
-                Some (action) =>{
-                    let board_played = &board.apply(&action);
-                    eval = self.playout(&board.apply(&action));
-                    return self.update_eval(board, &action, eval)
+            match self.select_ucb1(board) {
+                Some(action) => {
+                    let (ret, mut below) = self.playout(&board.apply(&action));
+                    // Back up the *raw* simulation return unchanged: every node/edge on the path
+                    // folds it into its own running mean, so a transposed position pools the
+                    // returns from all the parents that reach it rather than double-counting.
+                    self.update_eval(board, &action, ret, &below);
+                    // prepend this move so the parent sees it as part of its own sub-sequence
+                    below.insert(0, (board.turn, action));
+                    (ret, below)
                 }
-                None =>{
-                    println!("Error: No action available for this board: {board}");
-                    return eval;
+                None => {
+                    // Terminal node re-entered: no action to descend, just back up its final score.
+                    (white_score(board), Vec::new())
                 }
             }
-            
-        } ;
+        }
     }
 
     /// Updates the evaluation (Q(s)) of the board (s), after selected the action (a) for a new playout
-    /// which yieled an evaluation of `action_eval` (Q(s,a))
-    fn update_eval(&mut self, board: &Board, action: &Action, action_eval: f32) -> f32 {
+    /// which yielded the simulation return `return_value`.
+    ///
+    /// `below` is the sequence of moves played after `s` in this simulation; it drives the AMAF
+    /// update: every edge whose action was later played by the same player-to-move as `s` gets its
+    /// AMAF statistics refreshed with the simulation outcome.
+    ///
+    /// `return_value` is the raw simulation outcome (`white_score` at the leaf). It is folded into
+    /// honest running means, updated incrementally regardless of which parent triggered this visit:
+    /// `Q(s) = Σ returns / count` on the node and `Q(s,a) = Σ returns through a / visits` on the
+    /// edge. This is the proper transposition-table backup: a `Node` shared by several move orders
+    /// accumulates the pooled returns exactly once per visit.
+    fn update_eval(
+        &mut self,
+        board: &Board,
+        action: &Action,
+        return_value: f32,
+        below: &[(Color, Action)],
+    ) {
         debug_assert!(self.nodes.contains_key(board));
         let node = self.nodes.get_mut(board).unwrap();
-        let mut arc_store: &mut OutEdge;
+        let turn = node.board.turn;
+        // Running mean of every return that has reached this position, through any parent.
         node.count += 1;
-        node.eval = node.initial_eval/(node.count as f32) ;
-        for arc in node.out_edges.iter_mut(){
-            if arc.action.eq(action){
-                arc.visits +=1;
-                arc.eval = action_eval;
+        node.eval += (return_value - node.eval) / node.count as f32;
+        for arc in node.out_edges.iter_mut() {
+            if arc.action.eq(action) {
+                arc.visits += 1;
+                arc.eval += (return_value - arc.eval) / arc.visits as f32;
+            }
+            // AMAF: credit this edge if its action appears later in the simulation for this player.
+            if below
+                .iter()
+                .any(|(color, later)| *color == turn && later.eq(&arc.action))
+            {
+                arc.amaf_visits += 1;
+                arc.amaf_eval += (return_value - arc.amaf_eval) / arc.amaf_visits as f32;
             }
-            node.eval += ((arc.visits as f32)/(node.count as f32))* arc.eval;
         }
-        return node.eval;
     }
 }
 
 impl Engine for MctsEngine {
     fn select(&mut self, board: &Board, deadline: Instant) -> Option<Action> {
-        let time_remaining: bool = Instant::now() < deadline;
-        while(time_remaining){
-            let time_remaining: bool = Instant::now() < deadline;
-            self.playout(board);
+        if board.actions().is_empty() {
+            return None;
+        }
+
+        // Decisive-win shortcut: if an action leads directly to a final board already won for the
+        // side to move, commit to it immediately instead of spending playouts proving the obvious.
+        for action in board.actions() {
+            let child = board.apply(&action);
+            if child.is_draw() || child.actions().is_empty() {
+                let score = white_score(&child);
+                let decisive = match board.turn {
+                    Color::White => score == 1.,
+                    Color::Black => score == 0.,
+                };
+                if decisive {
+                    return Some(action);
+                }
+            }
+        }
+
+        // Collect the root edge statistics, either from N merged parallel searches or a single tree.
+        let edges: Vec<(Action, Count, f32)> = if self.num_threads > 1 {
+            self.search_root_parallel(board, deadline)
+        } else {
+            // Without tree reuse, drop the previous turn's tree so `nodes` stays bounded over a
+            // long game and we don't refine the root with stale cross-turn statistics.
+            if !self.reuse_tree {
+                self.nodes.clear();
+            }
+            while Instant::now() < deadline {
+                self.playout(board);
+            }
+            self.nodes
+                .get(board)
+                .map(|node| {
+                    node.out_edges
+                        .iter()
+                        .map(|e| (e.action.clone(), e.visits, e.eval))
+                        .collect_vec()
+                })
+                .unwrap_or_default()
+        };
+
+        // Final move rule: most-visited edge (robust against under-sampled high-variance edges),
+        // breaking ties by the eval most favourable to the side to move.
+        let turn = board.turn;
+        let best = edges.into_iter().max_by(|a, b| {
+            a.1.cmp(&b.1).then_with(|| {
+                let (ea, eb) = match turn {
+                    Color::White => (a.2, b.2),
+                    Color::Black => (b.2, a.2),
+                };
+                ea.partial_cmp(&eb).unwrap_or(std::cmp::Ordering::Equal)
+            })
+        });
+
+        let action = best.map(|(action, _, _)| action);
+
+        // When reusing the tree, prune down to the committed subtree so the next turn starts warm.
+        // The root-parallel path keeps its trees in per-worker maps and leaves `self.nodes` empty,
+        // so there is nothing to reuse — skip the prune rather than no-op against an empty map.
+        if self.reuse_tree && self.num_threads == 1 {
+            if let Some(action) = &action {
+                self.advance_root(&board.apply(action));
+            }
         }
-        return todo;
+
+        action
     }
 
     fn clear(&mut self) {
@@ -234,9 +470,11 @@ impl Engine for MctsEngine {
 
 #[cfg(test)]
 mod test {
+    use hashbrown::HashMap;
+
     use crate::Color;
 
-    use super::{Board, MctsEngine};
+    use super::{Board, Count, MctsEngine};
 
     #[test]
     fn test_mcts() {
@@ -263,4 +501,87 @@ mod test {
         }
         println!("{board}");
     }
+
+    /// A position reached through two different move orders is stored under a single `Board` key,
+    /// so a single `Node` must hold the returns pooled across *every* parent that reaches it — not
+    /// the statistics of one entry path. This test grows a tree, finds a board that is the target
+    /// of edges from two or more distinct parents (a genuine transposition), and asserts that the
+    /// shared node's `count`/`eval` equal the result pooled independently over all those in-edges.
+    #[test]
+    fn test_mcts_transposition() {
+        let board = Board::parse(
+            "
+              ABCDEFGH   White  (32 plies)
+            1  b . b b
+            2 . . . b
+            3  . . . w
+            4 . . . .
+            5  . . . .
+            6 . b w .
+            7  . . w .
+            8 w w w .",
+            Color::White,
+        );
+        let mut mcts = MctsEngine::new(1.);
+
+        // Run enough playouts that interior positions get reached through several move orders.
+        for _ in 0..3000 {
+            mcts.playout(&board);
+        }
+
+        // Index every edge by the board it leads to: child -> list of (visits, eval) in-edges.
+        let mut in_edges: HashMap<Board, Vec<(Count, f32)>> = HashMap::new();
+        for parent in mcts.nodes.values() {
+            for e in parent.out_edges.iter() {
+                if e.visits == 0 {
+                    continue;
+                }
+                in_edges
+                    .entry(parent.board.apply(&e.action))
+                    .or_default()
+                    .push((e.visits, e.eval));
+            }
+        }
+
+        // A transposition is a node reached via two or more distinct parent edges.
+        let mut checked = 0;
+        for node in mcts.nodes.values() {
+            let Some(edges) = in_edges.get(&node.board) else {
+                continue;
+            };
+            if edges.len() < 2 {
+                continue;
+            }
+            // Terminal nodes are inserted once and never re-`update_eval`d (their re-entries hit the
+            // `select_ucb1 -> None` branch), so their `count` stays 1 while in-edges keep accruing
+            // visits; the pooling identity only holds for nodes that are actually backed up.
+            if node.out_edges.is_empty() {
+                continue;
+            }
+            checked += 1;
+
+            // Every descent through any in-edge increments both that edge and the shared node once,
+            // so the node's visit count is exactly the pooled in-edge visits across all parents.
+            let pooled_visits: Count = edges.iter().map(|(v, _)| v).sum();
+            assert_eq!(
+                node.count, pooled_visits,
+                "shared node count must pool visits from every parent"
+            );
+
+            // Likewise its accumulated returns (eval * count) equal the returns pooled over the
+            // in-edges (each edge contributes eval * visits) — the DAG-correct transposition backup.
+            // Relative tolerance: these f32 sums grow into the hundreds over thousands of visits.
+            let pooled_returns: f32 = edges.iter().map(|(v, e)| *e * *v as f32).sum();
+            let node_total = node.eval * node.count as f32;
+            assert!(
+                (node_total - pooled_returns).abs() < 1e-2 * node_total.abs().max(1.0),
+                "shared node eval must equal pooled in-edge returns: {node_total} vs {pooled_returns}"
+            );
+        }
+
+        assert!(
+            checked > 0,
+            "expected at least one transposed position in the search tree"
+        );
+    }
 }
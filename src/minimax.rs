@@ -0,0 +1,172 @@
+use std::time::Instant;
+
+use crate::engine::Engine;
+use crate::mcts::white_score;
+
+use super::board::*;
+
+/// Minimax engine with alpha-beta pruning and iterative deepening.
+///
+/// Values are expressed from White's point of view in `[0, 1]` (like [`white_score`] and the
+/// MCTS evaluations), so White maximizes and Black minimizes. The search is driven by a real
+/// deadline: each depth is searched in turn and, as soon as the deadline is hit, the best move
+/// from the last *fully completed* depth is returned (the first legal move when not even depth 1
+/// was completed).
+pub struct MinimaxEngine {
+    /// Maximum depth the iterative deepening loop is allowed to reach.
+    max_depth: u32,
+    /// Depth of the last fully-completed iteration (0 before the first `select`).
+    last_depth_reached: u32,
+}
+
+impl MinimaxEngine {
+    pub fn new(max_depth: u32) -> MinimaxEngine {
+        MinimaxEngine {
+            max_depth,
+            last_depth_reached: 0,
+        }
+    }
+
+    /// Depth actually reached during the last call to [`Engine::select`].
+    ///
+    /// Useful to observe how the time control affects search strength: a tighter deadline yields a
+    /// shallower completed depth.
+    pub fn depth_reached(&self) -> u32 {
+        self.last_depth_reached
+    }
+
+    /// Static evaluation from White's perspective.
+    ///
+    /// Terminal boards are scored exactly with [`white_score`]. For depth-limited (non-terminal)
+    /// leaves we use a mobility proxy squashed into `(0, 1)`: having more moves available is good
+    /// for the side to move. This keeps the heuristic side-agnostic while giving alpha-beta a
+    /// gradient to prune on.
+    fn heuristic(board: &Board) -> f32 {
+        if board.is_draw() || board.actions().is_empty() {
+            return white_score(board);
+        }
+        let mobility = board.actions().len() as f32;
+        let signed = match board.turn {
+            Color::White => mobility,
+            Color::Black => -mobility,
+        };
+        0.5 + 0.5 * (signed / (1. + signed.abs()))
+    }
+
+    /// Alpha-beta search to the given `depth`. Returns `None` if the deadline was hit before a
+    /// value could be established (the caller must then discard this incomplete iteration).
+    fn alphabeta(
+        &self,
+        board: &Board,
+        depth: u32,
+        mut alpha: f32,
+        mut beta: f32,
+        deadline: Instant,
+    ) -> Option<f32> {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        let actions = board.actions();
+        if depth == 0 || actions.is_empty() || board.is_draw() {
+            return Some(Self::heuristic(board));
+        }
+        if board.turn == Color::White {
+            let mut value = f32::NEG_INFINITY;
+            for action in actions {
+                let child = board.apply(&action);
+                value = value.max(self.alphabeta(&child, depth - 1, alpha, beta, deadline)?);
+                alpha = alpha.max(value);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            Some(value)
+        } else {
+            let mut value = f32::INFINITY;
+            for action in actions {
+                let child = board.apply(&action);
+                value = value.min(self.alphabeta(&child, depth - 1, alpha, beta, deadline)?);
+                beta = beta.min(value);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            Some(value)
+        }
+    }
+}
+
+impl Engine for MinimaxEngine {
+    fn select(&mut self, board: &Board, deadline: Instant) -> Option<Action> {
+        let root_actions = board.actions();
+        if root_actions.is_empty() {
+            return None;
+        }
+
+        let maximizing = board.turn == Color::White;
+        let mut best_move = root_actions[0].clone();
+        self.last_depth_reached = 0;
+
+        for depth in 1..=self.max_depth {
+            // Move ordering: try the previous iteration's best move first so alpha-beta prunes more.
+            let mut ordered = root_actions.clone();
+            if let Some(pos) = ordered.iter().position(|a| a.eq(&best_move)) {
+                ordered.swap(0, pos);
+            }
+
+            let mut alpha = f32::NEG_INFINITY;
+            let mut beta = f32::INFINITY;
+            let mut depth_best: Option<Action> = None;
+            let mut depth_value = if maximizing {
+                f32::NEG_INFINITY
+            } else {
+                f32::INFINITY
+            };
+            let mut aborted = false;
+
+            for action in ordered {
+                if Instant::now() >= deadline {
+                    aborted = true;
+                    break;
+                }
+                let child = board.apply(&action);
+                match self.alphabeta(&child, depth - 1, alpha, beta, deadline) {
+                    Some(value) => {
+                        if maximizing {
+                            if depth_best.is_none() || value > depth_value {
+                                depth_value = value;
+                                depth_best = Some(action);
+                            }
+                            alpha = alpha.max(depth_value);
+                        } else {
+                            if depth_best.is_none() || value < depth_value {
+                                depth_value = value;
+                                depth_best = Some(action);
+                            }
+                            beta = beta.min(depth_value);
+                        }
+                    }
+                    None => {
+                        aborted = true;
+                        break;
+                    }
+                }
+            }
+
+            if aborted {
+                // This depth is incomplete: keep the best move from the last finished iteration.
+                break;
+            }
+            if let Some(bm) = depth_best {
+                best_move = bm;
+                self.last_depth_reached = depth;
+            }
+        }
+
+        Some(best_move)
+    }
+
+    fn clear(&mut self) {
+        self.last_depth_reached = 0;
+    }
+}